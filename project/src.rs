@@ -9,35 +9,178 @@ pub struct RandomResult {
     pub difference: i32,
 }
 
-// Linear congruential generator for pseudo-random numbers
+// PCG32 permuted congruential generator. See https://www.pcg-random.org/ for
+// the derivation of the multiplier, the xorshift, and the variable rotation.
+struct RngState {
+    state: u64,
+    inc: u64,
+    initialized: bool,
+}
+
+impl RngState {
+    const fn new() -> Self {
+        RngState { state: 0, inc: 0, initialized: false }
+    }
+}
+
 thread_local! {
-    static RNG_STATE: RefCell<u64> = RefCell::new(1);
+    static RNG_STATE: RefCell<RngState> = RefCell::new(RngState::new());
+}
+
+fn pcg32_next(rng: &mut RngState) -> u32 {
+    let old = rng.state;
+    rng.state = old.wrapping_mul(6364136223846793005).wrapping_add(rng.inc);
+    let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+    let rot = (old >> 59) as u32;
+    (xorshifted >> rot) | (xorshifted << (rot.wrapping_neg() & 31))
+}
+
+fn pcg32_seed(rng: &mut RngState, seed_state: u64, seed_sequence: u64) {
+    rng.inc = (seed_sequence << 1) | 1;
+    rng.state = 0;
+    pcg32_next(rng);
+    rng.state = rng.state.wrapping_add(seed_state);
+    pcg32_next(rng);
+    rng.initialized = true;
+}
+
+fn seed_from_time(rng: &mut RngState) {
+    let time_seed = ic_cdk::api::time();
+    pcg32_seed(rng, time_seed, time_seed);
 }
 
 fn generate_random_number() -> u32 {
     RNG_STATE.with(|state| {
         let mut rng = state.borrow_mut();
-        // Use current time as seed for better randomness
-        let time_seed = ic_cdk::api::time();
-        *rng = rng.wrapping_mul(1664525).wrapping_add(1013904223).wrapping_add(time_seed);
-        (*rng >> 16) as u32
+        if !rng.initialized {
+            seed_from_time(&mut rng);
+        }
+        pcg32_next(&mut rng)
     })
 }
 
+// Pool of entropy drawn from the management canister's `raw_rand`, analogous
+// to how `rand::rngs::OsRng` pulls from the OS entropy source. `raw_rand`
+// always returns 32 bytes; we only need 16 to reseed the generator, so the
+// leftover half is cached here and spent on the next call before we pay for
+// another inter-canister call.
+thread_local! {
+    static ENTROPY_POOL: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+}
+
+async fn take_entropy(len: usize) -> Result<Vec<u8>, String> {
+    loop {
+        let drained = ENTROPY_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() >= len {
+                Some(pool.drain(..len).collect())
+            } else {
+                None
+            }
+        });
+        if let Some(bytes) = drained {
+            return Ok(bytes);
+        }
+        let (fresh,): (Vec<u8>,) = ic_cdk::api::management_canister::main::raw_rand()
+            .await
+            .map_err(|(code, msg)| format!("raw_rand call failed: {code:?} - {msg}"))?;
+        ENTROPY_POOL.with(|pool| pool.borrow_mut().extend(fresh));
+    }
+}
+
 #[update]
-fn calculate_difference(user_input: i32) -> RandomResult {
-    // Generate random number between 1 and 100
-    let random_u32 = generate_random_number();
-    let random_number = (random_u32 % 100) as i32 + 1;
-    
-    // Calculate absolute difference
+async fn calculate_difference_secure(user_input: i32) -> Result<RandomResult, String> {
+    let entropy = take_entropy(16).await?;
+    let seed_state = u64::from_le_bytes(entropy[0..8].try_into().unwrap());
+    let seed_sequence = u64::from_le_bytes(entropy[8..16].try_into().unwrap());
+
+    RNG_STATE.with(|state| {
+        let mut rng = state.borrow_mut();
+        pcg32_seed(&mut rng, seed_state, seed_sequence);
+    });
+    let random_number = gen_range(1, 100);
+
     let difference = (user_input - random_number).abs();
-    
-    RandomResult {
+
+    Ok(RandomResult {
         user_input,
         random_number,
         difference,
+    })
+}
+
+// Number of integers in `[low, high]`, computed in `i64` so that ranges
+// spanning more than `i32::MAX` values don't overflow before the cast.
+// Returns `None` when the span doesn't fit in a `u32` (e.g. `low ==
+// i32::MIN, high == i32::MAX`), which callers should reject up front.
+fn range_span(low: i32, high: i32) -> Option<u32> {
+    u32::try_from(high as i64 - low as i64 + 1).ok()
+}
+
+// Draws an unbiased random integer in `[low, high]` via rejection sampling.
+// A plain `% span` is biased whenever `u32::MAX + 1` isn't a multiple of
+// `span`, since the low end of the range gets one extra draw; rejecting
+// anything past the last full multiple of `span` removes that bias.
+//
+// Callers must ensure `range_span(low, high)` fits in a `u32`.
+fn gen_range(low: i32, high: i32) -> i32 {
+    let span = range_span(low, high).expect("gen_range: span must fit in u32; validate the range first");
+    let zone = u32::MAX - (u32::MAX % span);
+
+    let mut value = generate_random_number();
+    while value >= zone {
+        value = generate_random_number();
     }
+
+    // `value % span` can exceed `i32::MAX` for a wide range (e.g. spanning
+    // most of the i32 domain), so add in i64 before narrowing; the sum is
+    // provably within `[low, high]`, so the final cast back to i32 is exact.
+    (low as i64 + (value % span) as i64) as i32
+}
+
+// Reseeds the generator explicitly, making subsequent draws reproducible until
+// the generator is reseeded again (by another `seed_rng`, or by
+// `calculate_difference_secure`, which reseeds it from `raw_rand`).
+// Useful for tests and for "provably fair" rounds where a client wants to
+// verify the sequence themselves via `get_rng_state`.
+#[update]
+fn seed_rng(seed_state: u64, seed_sequence: u64) {
+    RNG_STATE.with(|state| {
+        let mut rng = state.borrow_mut();
+        pcg32_seed(&mut rng, seed_state, seed_sequence);
+    });
+}
+
+#[query]
+fn get_rng_state() -> (u64, u64) {
+    RNG_STATE.with(|state| {
+        let rng = state.borrow();
+        (rng.state, rng.inc)
+    })
+}
+
+#[update]
+fn calculate_difference_in_range(user_input: i32, min: i32, max: i32) -> Result<RandomResult, String> {
+    if min >= max {
+        return Err(format!("invalid range: min ({min}) must be less than max ({max})"));
+    }
+    if range_span(min, max).is_none() {
+        return Err(format!("invalid range: span from {min} to {max} is too large to sample without bias"));
+    }
+
+    let random_number = gen_range(min, max);
+    let difference = (user_input - random_number).abs();
+
+    Ok(RandomResult {
+        user_input,
+        random_number,
+        difference,
+    })
+}
+
+#[update]
+fn calculate_difference(user_input: i32) -> RandomResult {
+    calculate_difference_in_range(user_input, 1, 100).expect("1..100 is always a valid range")
 }
 
 #[query]
@@ -45,5 +188,67 @@ fn get_info() -> String {
     "Random Number Difference Calculator - Built on Internet Computer".to_string()
 }
 
+// Non-uniform sampling built on top of `generate_random_number`, mirroring the
+// distributions the `rand` crate exposes for uniform-to-non-uniform transforms.
+mod distributions {
+    use super::generate_random_number;
+
+    // A uniform draw in (0, 1]. The numerator's `+ 1.0` keeps the value away
+    // from zero so `ln()` in the callers below stays finite; the denominator's
+    // `+ 1.0` keeps it at most 1.
+    fn uniform_open_unit() -> f64 {
+        (generate_random_number() as f64 + 1.0) / (u32::MAX as f64 + 1.0)
+    }
+
+    // Box-Muller transform: turns two uniform draws into a standard normal
+    // sample, then rescales to the requested mean and standard deviation.
+    pub fn sample_normal(mu: f64, sigma: f64) -> f64 {
+        let u1 = uniform_open_unit();
+        let u2 = uniform_open_unit();
+        let z0 = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        z0 * sigma + mu
+    }
+
+    // Inverse transform sampling for the exponential distribution.
+    pub fn sample_exponential(lambda: f64) -> f64 {
+        let u = uniform_open_unit();
+        -u.ln() / lambda
+    }
+}
+
+#[update]
+fn sample_normal(mu: f64, sigma: f64) -> f64 {
+    distributions::sample_normal(mu, sigma)
+}
+
+#[update]
+fn sample_exponential(lambda: f64) -> f64 {
+    distributions::sample_exponential(lambda)
+}
+
+const MAX_RANDOM_STRING_LEN: u32 = 1024;
+const ALNUM_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const HEX_CHARSET: &[u8] = b"0123456789ABCDEF";
+
+// Builds a fixed-length random token by drawing an unbiased index into
+// `charset` for each character, similar to how `urandom_str` mints ASCII
+// tokens from an entropy source.
+fn random_token(len: u32, charset: &[u8]) -> String {
+    let len = len.min(MAX_RANDOM_STRING_LEN);
+    (0..len)
+        .map(|_| charset[gen_range(0, charset.len() as i32 - 1) as usize] as char)
+        .collect()
+}
+
+#[update]
+fn random_string(len: u32) -> String {
+    random_token(len, ALNUM_CHARSET)
+}
+
+#[update]
+fn random_hex(len: u32) -> String {
+    random_token(len, HEX_CHARSET)
+}
+
 // Export Candid interface
 ic_cdk::export_candid!();
\ No newline at end of file